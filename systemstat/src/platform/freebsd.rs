@@ -4,9 +4,14 @@ use super::bsd;
 use super::common::*;
 use super::unix;
 use crate::data::*;
-use libc::{c_int, c_schar, c_uchar, c_void, size_t, sysctl, sysctlnametomib, timeval, uid_t};
+use libc::{
+    c_char, c_int, c_long, c_schar, c_uchar, c_uint, c_void, size_t, sysctl, sysctlnametomib,
+    time_t, timespec, timeval, uid_t,
+};
 use std::os::unix::ffi::OsStrExt;
-use std::{ffi, io, mem, path, ptr, slice, time};
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::{ffi, fs, io, mem, path, ptr, slice, time};
 
 pub struct PlatformImpl;
 
@@ -58,6 +63,9 @@ lazy_static! {
     static ref BATTERY_TIME: [c_int; 4] = sysctl_mib!(4, "hw.acpi.battery.time");
     static ref ACLINE: [c_int; 3] = sysctl_mib!(3, "hw.acpi.acline");
     static ref CPU0TEMP: [c_int; 4] = sysctl_mib!(4, "dev.cpu.0.temperature");
+    static ref KERN_DEVSTAT_ALL: [c_int; 3] = sysctl_mib!(3, "kern.devstat.all");
+    static ref VM_SWAP_TOTAL: [c_int; 2] = sysctl_mib!(2, "vm.swap_total");
+    static ref VM_SWAP_INFO: [c_int; 3] = sysctl_mib!(3, "vm.swap_info.0");
     static ref CP_TIMES_SIZE: usize = {
         let mut size: usize = 0;
         unsafe {
@@ -151,6 +159,9 @@ impl Platform for PlatformImpl {
     }
 
     fn mounts(&self) -> io::Result<Vec<Filesystem>> {
+        let getmntinfo = get_getmntinfo().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Other, "getmntinfo() is not available")
+        })?;
         let mut mptr: *mut statfs = ptr::null_mut();
         let len = unsafe { getmntinfo(&mut mptr, 1 as i32) };
         if len < 1 {
@@ -161,6 +172,8 @@ impl Platform for PlatformImpl {
     }
 
     fn mount_at<P: AsRef<path::Path>>(&self, path: P) -> io::Result<Filesystem> {
+        let statfs = get_statfs()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "statfs() is not available"))?;
         let mut sfs: statfs = unsafe { mem::zeroed() };
         if unsafe {
             statfs(
@@ -175,7 +188,26 @@ impl Platform for PlatformImpl {
     }
 
     fn block_device_statistics(&self) -> io::Result<BTreeMap<String, BlockDeviceStats>> {
-        Err(io::Error::new(io::ErrorKind::Other, "Not supported"))
+        let (_generation, entries) = try!(read_devstat_all());
+        let mut stats = BTreeMap::new();
+        for d in entries.iter() {
+            let name = unsafe { ffi::CStr::from_ptr(&d.device_name[0]) }.to_string_lossy();
+            if !looks_like_device_name(&name) {
+                continue;
+            }
+            let key = format!("{}{}", name, d.unit_number);
+            stats.insert(
+                key,
+                BlockDeviceStats {
+                    read_ios: d.operations[1],
+                    write_ios: d.operations[2],
+                    read_bytes: d.bytes[1],
+                    write_bytes: d.bytes[2],
+                    ..Default::default()
+                },
+            );
+        }
+        Ok(stats)
     }
 
     fn networks(&self) -> io::Result<BTreeMap<String, Network>> {
@@ -183,7 +215,18 @@ impl Platform for PlatformImpl {
     }
 
     fn network_stats(&self, interface: &str) -> io::Result<NetworkStats> {
-        Err(io::Error::new(io::ErrorKind::Other, "Not supported"))
+        let mut ifap: *mut ifaddrs = ptr::null_mut();
+        if unsafe { getifaddrs(&mut ifap) } != 0 {
+            return Err(io::Error::new(io::ErrorKind::Other, "getifaddrs() failed"));
+        }
+        let result = unsafe { find_network_stats(ifap, interface) };
+        unsafe { freeifaddrs(ifap) };
+        result.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "no AF_LINK entry found for interface",
+            )
+        })
     }
 
     fn cpu_temp(&self) -> io::Result<f32> {
@@ -199,6 +242,59 @@ impl Platform for PlatformImpl {
     }
 }
 
+impl PlatformImpl {
+    /// Reports swap usage, rounding out the picture `memory()` gives for RAM and ZFS ARC.
+    ///
+    /// Inherent rather than a `Platform` trait method: `VM_SWAP_TOTAL`/`VM_SWAP_INFO` are
+    /// FreeBSD-specific and no other backend in this crate reports swap today.
+    pub fn swap(&self) -> io::Result<Swap> {
+        let mut total_bytes: u64 = 0;
+        sysctl!(VM_SWAP_TOTAL, &mut total_bytes, mem::size_of::<u64>(), false);
+
+        let mut mib = *VM_SWAP_INFO;
+        let last = mib.len() - 1;
+        let mut total_blocks: u64 = 0;
+        let mut used_blocks: u64 = 0;
+        let mut n: c_int = 0;
+        loop {
+            mib[last] = n;
+            let mut dev: xswdev = unsafe { mem::zeroed() };
+            let mut size = mem::size_of::<xswdev>();
+            if unsafe {
+                sysctl(
+                    &mib[0],
+                    mib.len() as u32,
+                    &mut dev as *mut _ as *mut c_void,
+                    &mut size,
+                    ptr::null(),
+                    0,
+                )
+            } != 0
+            {
+                // ENOENT once we've walked past the last configured swap device.
+                break;
+            }
+            total_blocks += dev.xsw_nblks as u64;
+            used_blocks += dev.xsw_used as u64;
+            n += 1;
+        }
+
+        let total = if total_blocks > 0 {
+            ByteSize::kib(total_blocks << *bsd::PAGESHIFT)
+        } else {
+            // No enumerable swap devices (or we raced a config change); fall back to the
+            // kernel's own aggregate.
+            ByteSize::b(total_bytes)
+        };
+        let used = ByteSize::kib(used_blocks << *bsd::PAGESHIFT);
+        Ok(Swap {
+            total,
+            free: saturating_sub_bytes(total, used),
+            platform_swap: PlatformSwap { used },
+        })
+    }
+}
+
 fn measure_cpu() -> io::Result<Vec<CpuTime>> {
     let cpus = *CP_TIMES_SIZE / mem::size_of::<bsd::sysctl_cpu>();
     let mut data: Vec<bsd::sysctl_cpu> = Vec::with_capacity(cpus);
@@ -214,6 +310,137 @@ fn zfs_arc_size() -> io::Result<u64> {
     Ok(zfs_arc as u64)
 }
 
+const DEVSTAT_NAME_LEN: usize = 16;
+const DEVSTAT_N_TRANS_FLAGS: usize = 4;
+
+// `struct bintime` from <sys/time.h>: a seconds/fractional-seconds pair used throughout
+// `struct devstat` for the busy/duration/creation timers.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct bintime {
+    sec: time_t,
+    frac: u64,
+}
+
+// Mirrors struct devstat from <sys/devicestat.h>. `device_name` is assumed to be the inline
+// `char device_name[DEVSTAT_NAME_LEN]` later releases use (vs. a kernel-space `const char *`
+// on older ones) — `looks_like_device_name()` guards against that assumption being wrong.
+// Unverified against a real `<sys/devicestat.h>`/`bindgen` dump; treat every offset as suspect.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct devstat {
+    sequence0: c_uint,
+    allocated: c_int,
+    start_count: c_uint,
+    end_count: c_uint,
+    busy_from: bintime,
+    dev_links: *mut c_void, // STAILQ_ENTRY(devstat): a single forward `next` pointer.
+    device_number: u32,
+    device_name: [c_schar; DEVSTAT_NAME_LEN],
+    unit_number: c_int,
+    bytes: [u64; DEVSTAT_N_TRANS_FLAGS],
+    operations: [u64; DEVSTAT_N_TRANS_FLAGS],
+    duration: [[u64; 2]; DEVSTAT_N_TRANS_FLAGS],
+    busy_time: [u64; 2],
+    creation_time: [u64; 2],
+    block_size: u32,
+    tag_types: [c_long; 3],
+    flags: c_int,
+    device_type: c_int,
+    last_error_type: c_int,
+    priority: c_int,
+    id: *mut c_void,
+    sequence1: c_uint,
+}
+
+// `kern.devstat.all` returns a leading generation `long` followed by a packed array of
+// `struct devstat`. The device count (and hence the buffer size) can change between the size
+// probe and the real read as devices attach/detach, so pad the allocation a little and
+// truncate to whatever actually came back.
+fn read_devstat_all() -> io::Result<(c_long, Vec<devstat>)> {
+    let mut size: usize = 0;
+    if unsafe {
+        sysctl(
+            &KERN_DEVSTAT_ALL[0],
+            KERN_DEVSTAT_ALL.len() as u32,
+            ptr::null_mut(),
+            &mut size,
+            ptr::null(),
+            0,
+        )
+    } != 0
+    {
+        return Err(io::Error::new(io::ErrorKind::Other, "sysctl() failed"));
+    }
+    size += mem::size_of::<devstat>() * 4;
+
+    let mut buf: Vec<u8> = vec![0u8; size];
+    let mut len = size;
+    if unsafe {
+        sysctl(
+            &KERN_DEVSTAT_ALL[0],
+            KERN_DEVSTAT_ALL.len() as u32,
+            buf.as_mut_ptr() as *mut c_void,
+            &mut len,
+            ptr::null(),
+            0,
+        )
+    } != 0
+    {
+        return Err(io::Error::new(io::ErrorKind::Other, "sysctl() failed"));
+    }
+    buf.truncate(len);
+    Ok(parse_devstat_buffer(&buf))
+}
+
+// Split out from `read_devstat_all` so the parsing can be exercised with a hand-built buffer
+// without a live sysctl call. Reads each `devstat` with `ptr::read_unaligned` rather than
+// forming a `&[devstat]` over `body`: `body` is a sub-slice of a `Vec<u8>`, which only
+// guarantees 1-byte alignment, while `devstat` has 8-byte-aligned fields (`time_t`, pointers).
+fn parse_devstat_buffer(buf: &[u8]) -> (c_long, Vec<devstat>) {
+    let generation: c_long = unsafe { ptr::read_unaligned(buf.as_ptr() as *const c_long) };
+    let body = &buf[mem::size_of::<c_long>()..];
+    let count = body.len() / mem::size_of::<devstat>();
+    let entries = (0..count)
+        .map(|i| unsafe { ptr::read_unaligned((body.as_ptr() as *const devstat).add(i)) })
+        .collect();
+    (generation, entries)
+}
+
+// Guards against the `device_name` layout assumption above being wrong: misaligned bytes (e.g.
+// a kernel pointer) are unlikely to look like a device name. `device_name` sits before
+// `unit_number`, `bytes` and `operations`, so passing this only shows the fields up to and
+// including `device_name` lined up; the counters after it are not checked.
+fn looks_like_device_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Swap usage, reported by [`PlatformImpl::swap`].
+pub struct Swap {
+    pub total: ByteSize,
+    pub free: ByteSize,
+    pub platform_swap: PlatformSwap,
+}
+
+/// FreeBSD-specific swap details.
+pub struct PlatformSwap {
+    pub used: ByteSize,
+}
+
+// Mirrors `struct xswdev` from <vm/vm_param.h>, returned by the `vm.swap_info.N` sysctl
+// series (one entry per configured swap device).
+#[repr(C)]
+struct xswdev {
+    xsw_version: u32,
+    xsw_dev: u64,
+    xsw_flags: c_int,
+    xsw_nblks: c_int,
+    xsw_used: c_int,
+}
+
 #[repr(C)]
 struct fsid_t {
     val: [i32; 2],
@@ -221,6 +448,11 @@ struct fsid_t {
 
 // FreeBSD's native struct. If you want to know what FreeBSD
 // thinks about the POSIX statvfs struct, read man 3 statvfs :D
+//
+// This is the ino64 layout FreeBSD >= 12 exports as the unversioned `statfs`/`getmntinfo`
+// symbols; the only difference from the pre-ino64 `freebsd11_statfs` is `f_mntfromname`/
+// `f_mntonname` growing from 88 to `MNAMELEN` = 1024 bytes. Sized for the larger of the two
+// since `resolve_symbol` below isn't pinned to either version.
 #[repr(C)]
 struct statfs {
     f_version: u32,
@@ -243,8 +475,8 @@ struct statfs {
     f_fsid: fsid_t,
     f_charspare: [c_schar; 80],
     f_fstypename: [c_schar; 16],
-    f_mntfromname: [c_schar; 88],
-    f_mntonname: [c_schar; 88],
+    f_mntfromname: [c_schar; 1024],
+    f_mntonname: [c_schar; 1024],
 }
 
 impl statfs {
@@ -278,8 +510,478 @@ impl statfs {
 
 #[link(name = "c")]
 extern "C" {
-    #[link_name = "getmntinfo@FBSD_1.0"]
-    fn getmntinfo(mntbufp: *mut *mut statfs, flags: c_int) -> c_int;
-    #[link_name = "statfs@FBSD_1.0"]
-    fn statfs(path: *const c_uchar, buf: *mut statfs) -> c_int;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn getifaddrs(ifap: *mut *mut ifaddrs) -> c_int;
+    fn freeifaddrs(ifp: *mut ifaddrs);
+}
+
+// ((void *)-2), from <dlfcn.h>: search the default set of shared objects for the symbol.
+const RTLD_DEFAULT: *mut c_void = -2isize as *mut c_void;
+
+type GetMntInfoFn = unsafe extern "C" fn(*mut *mut statfs, c_int) -> c_int;
+type StatFsFn = unsafe extern "C" fn(*const c_uchar, *mut statfs) -> c_int;
+
+static GETMNTINFO_CACHE: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+static STATFS_CACHE: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+
+// Resolves a libc symbol by name at runtime and caches the resolved address, instead of
+// linking against a specific versioned symbol (e.g. `getmntinfo@FBSD_1.0`) — FreeBSD's libc
+// doesn't export `dlvsym` to pin one. `struct statfs` is sized for the largest layout any
+// resolved version can hand back (see the comment there), so a `statfs()` call can't overrun
+// its buffer; `getmntinfo()`'s array is read with this layout's stride, which matches only
+// the ino64 symbol.
+fn resolve_symbol(cache: &AtomicPtr<c_void>, name: &'static [u8]) -> Option<*mut c_void> {
+    let cached = cache.load(Ordering::Acquire);
+    if !cached.is_null() {
+        return Some(cached);
+    }
+    let cname = ffi::CStr::from_bytes_with_nul(name).unwrap();
+    let resolved = unsafe { dlsym(RTLD_DEFAULT, cname.as_ptr()) };
+    if resolved.is_null() {
+        return None;
+    }
+    cache.store(resolved, Ordering::Release);
+    Some(resolved)
+}
+
+fn get_getmntinfo() -> Option<GetMntInfoFn> {
+    resolve_symbol(&GETMNTINFO_CACHE, b"getmntinfo\0")
+        .map(|sym| unsafe { mem::transmute::<*mut c_void, GetMntInfoFn>(sym) })
+}
+
+fn get_statfs() -> Option<StatFsFn> {
+    resolve_symbol(&STATFS_CACHE, b"statfs\0")
+        .map(|sym| unsafe { mem::transmute::<*mut c_void, StatFsFn>(sym) })
+}
+
+// AF_LINK, from <sys/socket.h>.
+const AF_LINK: c_int = 18;
+
+// Just enough of `struct sockaddr` (<sys/socket.h>) to read the address family.
+#[repr(C)]
+struct sockaddr_min {
+    sa_len: u8,
+    sa_family: u8,
+}
+
+// Mirrors `struct ifaddrs` from <ifaddrs.h>.
+#[repr(C)]
+struct ifaddrs {
+    ifa_next: *mut ifaddrs,
+    ifa_name: *mut c_char,
+    ifa_flags: c_uint,
+    ifa_addr: *mut sockaddr_min,
+    ifa_netmask: *mut sockaddr_min,
+    ifa_dstaddr: *mut sockaddr_min,
+    ifa_data: *mut c_void,
+}
+
+// Mirrors `struct if_data` from <net/if.h>: when `ifa_addr->sa_family == AF_LINK`,
+// `ifa_data` points at one of these with the interface's packet/byte counters.
+#[repr(C)]
+struct if_data {
+    ifi_type: u8,
+    ifi_physical: u8,
+    ifi_addrlen: u8,
+    ifi_hdrlen: u8,
+    ifi_link_state: u8,
+    ifi_vhid: u8,
+    ifi_datalen: u16,
+    ifi_mtu: u32,
+    ifi_metric: u32,
+    ifi_baudrate: u64,
+    ifi_ipackets: u64,
+    ifi_ierrors: u64,
+    ifi_opackets: u64,
+    ifi_oerrors: u64,
+    ifi_collisions: u64,
+    ifi_ibytes: u64,
+    ifi_obytes: u64,
+    ifi_imcasts: u64,
+    ifi_omcasts: u64,
+    ifi_iqdrops: u64,
+    ifi_oqdrops: u64,
+    ifi_noproto: u64,
+    ifi_hwassist: u64,
+    ifi_epoch: i64,
+    ifi_lastchange: [i64; 2],
+}
+
+// Walks the `getifaddrs(3)` linked list looking for the `AF_LINK` entry for `interface`. Split
+// out from `network_stats()` as a pure function (it takes the already-populated list rather
+// than calling `getifaddrs`/`freeifaddrs` itself) so it can be exercised with a hand-built list
+// instead of requiring live interfaces.
+unsafe fn find_network_stats(ifap: *const ifaddrs, interface: &str) -> Option<NetworkStats> {
+    let mut cur = ifap;
+    while !cur.is_null() {
+        let ifa = &*cur;
+        let name = ffi::CStr::from_ptr(ifa.ifa_name).to_string_lossy();
+        if name == interface
+            && !ifa.ifa_addr.is_null()
+            && (*ifa.ifa_addr).sa_family as c_int == AF_LINK
+            && !ifa.ifa_data.is_null()
+        {
+            let data = &*(ifa.ifa_data as *const if_data);
+            return Some(NetworkStats {
+                rx_bytes: ByteSize::b(data.ifi_ibytes),
+                tx_bytes: ByteSize::b(data.ifi_obytes),
+                rx_packets: data.ifi_ipackets,
+                tx_packets: data.ifi_opackets,
+                rx_errors: data.ifi_ierrors,
+                tx_errors: data.ifi_oerrors,
+            });
+        }
+        cur = ifa.ifa_next;
+    }
+    None
+}
+
+// Everything below this point is pull-only metrics turned into a push-style subscription: a
+// kqueue watching the root mount point ("/") for vnode writes, re-checked against `mounts()`
+// since ordinary directory traffic triggers the same event, plus a periodic timer note that
+// re-reads the ACPI sysctls. Hand-rolled so this file doesn't gain a `nix` dependency.
+
+const EVFILT_VNODE: i16 = -4;
+const EVFILT_TIMER: i16 = -7;
+const EV_ADD: u16 = 0x0001;
+const EV_CLEAR: u16 = 0x0020;
+const NOTE_WRITE: u32 = 0x0002;
+const NOTE_EXTEND: u32 = 0x0004;
+
+// At or below this remaining battery fraction, `BatteryLow` fires (once, on the crossing).
+const BATTERY_LOW_THRESHOLD: f32 = 0.2;
+// How often the timer note wakes us up to re-check AC power / battery level.
+const POWER_POLL_INTERVAL_MS: isize = 2000;
+
+// Mirrors the current `struct kevent` from <sys/event.h>, including the trailing `ext[4]`
+// array the old (`freebsd11_kevent`-compat) ABI lacks — omitting it would size `ev` in
+// `wait()` too small for the unversioned `kevent`/`kqueue` symbols this links against.
+#[repr(C)]
+struct kevent_t {
+    ident: usize,
+    filter: i16,
+    flags: u16,
+    fflags: u32,
+    data: isize,
+    udata: *mut c_void,
+    ext: [u64; 4],
+}
+
+#[link(name = "c")]
+extern "C" {
+    fn kqueue() -> c_int;
+    fn kevent(
+        kq: c_int,
+        changelist: *const kevent_t,
+        nchanges: c_int,
+        eventlist: *mut kevent_t,
+        nevents: c_int,
+        timeout: *const timespec,
+    ) -> c_int;
+    fn close(fd: c_int) -> c_int;
+}
+
+/// An event surfaced by [`Watcher`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WatchEvent {
+    /// The mount table actually changed: a filesystem was mounted or unmounted under the
+    /// watched root (`/`). This is diffed against the previous `mounts()` snapshot, so ordinary
+    /// writes under `/` that don't touch the mount table are filtered out rather than reported
+    /// as spurious events.
+    MountsChanged,
+    /// AC power was plugged or unplugged; carries the new `on_ac_power()` state.
+    AcPowerChanged(bool),
+    /// Battery remaining capacity dropped to or below [`BATTERY_LOW_THRESHOLD`]; carries the level.
+    BatteryLow(f32),
+}
+
+/// A reactive alternative to polling `mounts()`/`on_ac_power()`/`battery_life()`, built on
+/// kqueue. Create one with [`watch`].
+pub struct Watcher {
+    kq: c_int,
+    // Kept open only so its fd (and the EVFILT_VNODE note registered against it) stays alive.
+    _mount_root: fs::File,
+    last_mounts: Vec<String>,
+    last_on_ac: bool,
+    last_battery: f32,
+}
+
+/// Opens a kqueue and registers watches for mount-table changes and AC/battery state.
+pub fn watch() -> io::Result<Watcher> {
+    let kq = unsafe { kqueue() };
+    if kq < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mount_root = fs::File::open("/")?;
+
+    let platform = PlatformImpl;
+    let last_mounts = mount_points(&platform);
+    let last_on_ac = platform.on_ac_power().unwrap_or(true);
+    let last_battery = platform
+        .battery_life()
+        .map(|b| b.remaining_capacity)
+        .unwrap_or(1.0);
+
+    let changes = [
+        kevent_t {
+            ident: mount_root.as_raw_fd() as usize,
+            filter: EVFILT_VNODE,
+            flags: EV_ADD | EV_CLEAR,
+            fflags: NOTE_WRITE | NOTE_EXTEND,
+            data: 0,
+            udata: ptr::null_mut(),
+            ext: [0; 4],
+        },
+        kevent_t {
+            ident: 1,
+            filter: EVFILT_TIMER,
+            flags: EV_ADD | EV_CLEAR,
+            fflags: 0,
+            data: POWER_POLL_INTERVAL_MS,
+            udata: ptr::null_mut(),
+            ext: [0; 4],
+        },
+    ];
+    if unsafe {
+        kevent(
+            kq,
+            changes.as_ptr(),
+            changes.len() as c_int,
+            ptr::null_mut(),
+            0,
+            ptr::null(),
+        )
+    } < 0
+    {
+        let err = io::Error::last_os_error();
+        unsafe { close(kq) };
+        return Err(err);
+    }
+
+    Ok(Watcher {
+        kq,
+        _mount_root: mount_root,
+        last_mounts,
+        last_on_ac,
+        last_battery,
+    })
+}
+
+// The sorted list of current mount points, used as the snapshot `check_mounts` diffs against.
+// Sorted because `mounts()`'s ordering follows `getmntinfo`'s (kernel-internal, not meaningful
+// here), and an unsorted comparison would treat a same-set reordering as a change.
+fn mount_points(platform: &PlatformImpl) -> Vec<String> {
+    let mut points: Vec<String> = platform
+        .mounts()
+        .map(|fses| fses.into_iter().map(|fs| fs.fs_mounted_on).collect())
+        .unwrap_or_default();
+    points.sort();
+    points
+}
+
+impl Watcher {
+    /// Blocks until an event occurs.
+    pub fn next_event(&mut self) -> io::Result<WatchEvent> {
+        loop {
+            if let Some(event) = self.wait(ptr::null())? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Returns an event if one is immediately available, without blocking.
+    pub fn poll(&mut self) -> io::Result<Option<WatchEvent>> {
+        let timeout = timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        self.wait(&timeout)
+    }
+
+    fn wait(&mut self, timeout: *const timespec) -> io::Result<Option<WatchEvent>> {
+        let mut ev: kevent_t = unsafe { mem::zeroed() };
+        let n = unsafe { kevent(self.kq, ptr::null(), 0, &mut ev, 1, timeout) };
+        if n < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if n == 0 {
+            return Ok(None);
+        }
+        match ev.filter {
+            EVFILT_VNODE => Ok(self.check_mounts()),
+            EVFILT_TIMER => Ok(self.check_power_state()),
+            _ => Ok(None),
+        }
+    }
+
+    // "/"'s vnode fires NOTE_WRITE/NOTE_EXTEND for mount(2)/unmount(2), but also for ordinary
+    // writes into the root directory itself (e.g. creating a file there), so every wakeup is
+    // re-checked against a fresh mounts() snapshot and only a genuine change is surfaced.
+    fn check_mounts(&mut self) -> Option<WatchEvent> {
+        let platform = PlatformImpl;
+        let mounts = mount_points(&platform);
+        if mounts == self.last_mounts {
+            return None;
+        }
+        self.last_mounts = mounts;
+        Some(WatchEvent::MountsChanged)
+    }
+
+    fn check_power_state(&mut self) -> Option<WatchEvent> {
+        let platform = PlatformImpl;
+        let on_ac = platform.on_ac_power().unwrap_or(self.last_on_ac);
+        let ac_event = ac_power_event(self.last_on_ac, on_ac);
+        self.last_on_ac = on_ac;
+        if ac_event.is_some() {
+            return ac_event;
+        }
+
+        if let Ok(life) = platform.battery_life() {
+            let level = life.remaining_capacity;
+            let battery_event = battery_low_event(self.last_battery, level);
+            self.last_battery = level;
+            return battery_event;
+        }
+
+        None
+    }
+}
+
+// Pulled out of `check_power_state` as pure functions so the threshold-crossing logic can be
+// tested without a live kqueue/ACPI sysctl.
+fn ac_power_event(last_on_ac: bool, now_on_ac: bool) -> Option<WatchEvent> {
+    if now_on_ac != last_on_ac {
+        Some(WatchEvent::AcPowerChanged(now_on_ac))
+    } else {
+        None
+    }
+}
+
+fn battery_low_event(last_level: f32, now_level: f32) -> Option<WatchEvent> {
+    if now_level <= BATTERY_LOW_THRESHOLD && last_level > BATTERY_LOW_THRESHOLD {
+        Some(WatchEvent::BatteryLow(now_level))
+    } else {
+        None
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        unsafe { close(self.kq) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn devstat_bytes(name: &str, unit: c_int, bytes: [u64; 4], operations: [u64; 4]) -> Vec<u8> {
+        let mut d: devstat = unsafe { mem::zeroed() };
+        for (i, b) in name.bytes().enumerate() {
+            d.device_name[i] = b as c_schar;
+        }
+        d.unit_number = unit;
+        d.bytes = bytes;
+        d.operations = operations;
+        unsafe { slice::from_raw_parts(&d as *const devstat as *const u8, mem::size_of::<devstat>()) }
+            .to_vec()
+    }
+
+    #[test]
+    fn parse_devstat_buffer_reads_generation_and_entries() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&42i64.to_ne_bytes());
+        buf.extend_from_slice(&devstat_bytes("ada0", 0, [0, 111, 222, 0], [0, 11, 22, 0]));
+        buf.extend_from_slice(&devstat_bytes("da1", 1, [0, 1, 2, 0], [0, 3, 4, 0]));
+
+        let (generation, entries) = parse_devstat_buffer(&buf);
+        assert_eq!(generation, 42);
+        assert_eq!(entries.len(), 2);
+
+        let name0 = unsafe { ffi::CStr::from_ptr(&entries[0].device_name[0]) }
+            .to_string_lossy()
+            .into_owned();
+        assert_eq!(name0, "ada0");
+        assert_eq!(entries[0].bytes[1], 111);
+        assert_eq!(entries[0].operations[2], 22);
+        assert_eq!(entries[1].unit_number, 1);
+    }
+
+    #[test]
+    fn looks_like_device_name_rejects_non_name_bytes() {
+        assert!(looks_like_device_name("ada0"));
+        assert!(!looks_like_device_name(""));
+        assert!(!looks_like_device_name("ad a"));
+        assert!(!looks_like_device_name("ad\u{0}a"));
+    }
+
+    #[test]
+    fn find_network_stats_matches_the_af_link_entry() {
+        let mut data: if_data = unsafe { mem::zeroed() };
+        data.ifi_ibytes = 100;
+        data.ifi_obytes = 200;
+
+        let mut sa = sockaddr_min {
+            sa_len: 0,
+            sa_family: AF_LINK as u8,
+        };
+
+        let em0_name = ffi::CString::new("em0").unwrap();
+        let mut em0 = ifaddrs {
+            ifa_next: ptr::null_mut(),
+            ifa_name: em0_name.as_ptr() as *mut c_char,
+            ifa_flags: 0,
+            ifa_addr: &mut sa,
+            ifa_netmask: ptr::null_mut(),
+            ifa_dstaddr: ptr::null_mut(),
+            ifa_data: &mut data as *mut _ as *mut c_void,
+        };
+
+        let lo0_name = ffi::CString::new("lo0").unwrap();
+        let mut lo0 = ifaddrs {
+            ifa_next: &mut em0,
+            ifa_name: lo0_name.as_ptr() as *mut c_char,
+            ifa_flags: 0,
+            ifa_addr: ptr::null_mut(),
+            ifa_netmask: ptr::null_mut(),
+            ifa_dstaddr: ptr::null_mut(),
+            ifa_data: ptr::null_mut(),
+        };
+
+        let stats = unsafe { find_network_stats(&mut lo0, "em0") }.unwrap();
+        assert_eq!(stats.rx_bytes, ByteSize::b(100));
+        assert_eq!(stats.tx_bytes, ByteSize::b(200));
+        assert!(unsafe { find_network_stats(&mut lo0, "nonexistent") }.is_none());
+    }
+
+    #[test]
+    fn resolve_symbol_returns_the_cached_pointer_without_calling_dlsym() {
+        static CACHE: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+        let sentinel = 0x1234usize as *mut c_void;
+        CACHE.store(sentinel, Ordering::Release);
+        // A name `dlsym` would never resolve, so a `None` result here would mean the cache was
+        // bypassed.
+        let resolved = resolve_symbol(&CACHE, b"__systemstat_freebsd_test_sentinel__\0");
+        assert_eq!(resolved, Some(sentinel));
+    }
+
+    #[test]
+    fn ac_power_event_fires_only_on_change() {
+        assert_eq!(ac_power_event(true, true), None);
+        assert_eq!(
+            ac_power_event(true, false),
+            Some(WatchEvent::AcPowerChanged(false))
+        );
+    }
+
+    #[test]
+    fn battery_low_event_fires_once_on_the_crossing() {
+        assert_eq!(
+            battery_low_event(0.5, 0.1),
+            Some(WatchEvent::BatteryLow(0.1))
+        );
+        // Already below the threshold last time; shouldn't re-fire every tick.
+        assert_eq!(battery_low_event(0.1, 0.05), None);
+        assert_eq!(battery_low_event(0.5, 0.9), None);
+    }
 }